@@ -0,0 +1,28 @@
+use serde::Deserialize;
+use std::path::Path;
+
+/// Everything that can be set in a `--config` TOML file. Every field mirrors
+/// a CLI flag and stays optional: a field left out of the file keeps falling
+/// back to the built-in default (`bucket` has none and must be set in one of
+/// the two places), and any flag actually passed on the command line
+/// overrides whatever the file says.
+#[derive(Deserialize, Debug, Default, Clone)]
+pub struct FileConfig {
+    pub bucket: Option<String>,
+    pub region: Option<String>,
+    pub endpoint: Option<String>,
+    pub mode: Option<String>,
+    pub backend: Option<String>,
+    pub max_retries: Option<u32>,
+    pub listen: Option<String>,
+    pub presign_expiry_secs: Option<u64>,
+}
+
+impl FileConfig {
+    pub fn load(path: &Path) -> Result<Self, String> {
+        let contents = std::fs::read_to_string(path)
+            .map_err(|e| format!("failed to read config file {}: {}", path.display(), e))?;
+        toml::from_str(&contents)
+            .map_err(|e| format!("failed to parse config file {}: {}", path.display(), e))
+    }
+}
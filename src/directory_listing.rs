@@ -1,9 +1,8 @@
-use crate::utils::{get_parent, url_encode};
+use crate::object_store::{ObjectMeta, ObjectStore, ObjectStoreError};
+use crate::utils::{get_parent, human_size, url_encode};
 use handlebars::{Handlebars, RenderError};
-use log::warn;
-use rusoto_core::RusotoError;
-use rusoto_s3::{ListObjectsV2Error, ListObjectsV2Request, S3Client, S3};
 use serde::Serialize;
+use std::str::FromStr;
 use thiserror::Error;
 use warp::{reject::Reject, Rejection};
 
@@ -13,16 +12,67 @@ const DIR_LIST_TEMPLATE: &'static str = include_str!("directory_listing.hbs");
 enum DirectoryListingError {
     #[error("template error")]
     TemplateError(#[from] RenderError),
-    #[error("s3 error")]
-    S3Error(#[from] RusotoError<ListObjectsV2Error>),
+    #[error("object store error")]
+    Store(#[from] ObjectStoreError),
 }
 impl Reject for DirectoryListingError {}
 
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum SortKey {
+    Name,
+    Size,
+    Mtime,
+}
+
+impl FromStr for SortKey {
+    type Err = String;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "name" => Ok(SortKey::Name),
+            "size" => Ok(SortKey::Size),
+            "mtime" => Ok(SortKey::Mtime),
+            _ => Err(format!("unknown sort key '{}'", s)),
+        }
+    }
+}
+
+impl Default for SortKey {
+    fn default() -> Self {
+        SortKey::Name
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum SortOrder {
+    Asc,
+    Desc,
+}
+
+impl FromStr for SortOrder {
+    type Err = String;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "asc" => Ok(SortOrder::Asc),
+            "desc" => Ok(SortOrder::Desc),
+            _ => Err(format!("unknown sort order '{}'", s)),
+        }
+    }
+}
+
+impl Default for SortOrder {
+    fn default() -> Self {
+        SortOrder::Asc
+    }
+}
+
 // TODO move directory listing stuff to separate file
 #[derive(Serialize)]
 struct DirectoryListingItem {
     name: String,
     url: String,
+    size: Option<u64>,
+    human_size: String,
+    last_modified: Option<String>,
 }
 
 #[derive(Serialize)]
@@ -33,6 +83,23 @@ struct DirectoryListingData<'a> {
     items: Vec<DirectoryListingItem>,
 }
 
+// Split out of `directory_listing` so the comparator logic is testable
+// without a fake `ObjectStore`.
+fn sort_files(files: &mut Vec<ObjectMeta>, sort: SortKey, order: SortOrder) {
+    files.sort_by(|a, b| match sort {
+        SortKey::Name => a.name.cmp(&b.name),
+        SortKey::Size => a.size.unwrap_or(0).cmp(&b.size.unwrap_or(0)),
+        SortKey::Mtime => a
+            .last_modified
+            .as_deref()
+            .unwrap_or("")
+            .cmp(b.last_modified.as_deref().unwrap_or("")),
+    });
+    if order == SortOrder::Desc {
+        files.reverse();
+    }
+}
+
 pub struct DirectoryLister {
     handlebars: Handlebars<'static>,
 }
@@ -50,70 +117,44 @@ impl DirectoryLister {
     pub async fn directory_listing(
         &self,
         base: &str,
-        s3: &S3Client,
-        bucket: &str,
+        store: &dyn ObjectStore,
+        sort: SortKey,
+        order: SortOrder,
     ) -> Result<Box<dyn warp::Reply>, Rejection> {
         debug_assert!(base.is_empty() || base.ends_with('/'));
-        let mut dirs: Vec<String> = vec![];
-        let mut files: Vec<String> = vec![];
-        let mut continuation_token = None;
-        loop {
-            // TODO use pagination
-            let list = s3
-                .list_objects_v2(ListObjectsV2Request {
-                    bucket: bucket.to_string(),
-                    prefix: Some(base.into()),
-                    delimiter: Some("/".into()),
-                    continuation_token: continuation_token.take(),
-                    ..Default::default()
-                })
-                .await
-                .map_err(DirectoryListingError::S3Error)?;
-            continuation_token = list.next_continuation_token;
-            if let Some(common) = list.common_prefixes {
-                dirs.extend(common.into_iter().filter_map(|c| {
-                    let p = c.prefix.or_else(|| {
-                        warn!("none in s3 listing common_prefixes");
-                        None
-                    })?;
-                    p.strip_prefix(base).map(Into::into).or_else(|| {
-                        warn!("common prefix without expected prefix found ({})", p);
-                        None
-                    })
-                }));
-            }
-            if let Some(contents) = list.contents {
-                files.extend(contents.into_iter().filter_map(|c| -> Option<String> {
-                    let key = c.key.or_else(|| {
-                        warn!("none key in s3 listing contents");
-                        None
-                    })?;
-                    if key.ends_with('/') {
-                        warn!("key ending with / found ({})", key);
-                        return None;
-                    }
-                    key.strip_prefix(base).map(Into::into).or_else(|| {
-                        warn!("key without expected prefix found ({})", key);
-                        None
-                    })
-                }));
-            }
-            if continuation_token.is_none() {
-                break;
-            }
-        }
+        let (mut dirs, mut files): (Vec<String>, Vec<ObjectMeta>) = store
+            .list(base)
+            .await
+            .map_err(DirectoryListingError::Store)?;
         if dirs.is_empty() && files.is_empty() {
             Err(warp::reject::not_found())
         } else {
             let get_url = |name: &str| url_encode(&format!("/{}{}", base, name));
+            dirs.sort();
+            if order == SortOrder::Desc {
+                dirs.reverse();
+            }
+            sort_files(&mut files, sort, order);
             let mut items = Vec::with_capacity(dirs.len() + files.len());
             items.extend(dirs.into_iter().map(|name| {
                 let url = get_url(&name);
-                DirectoryListingItem { name, url }
+                DirectoryListingItem {
+                    name,
+                    url,
+                    size: None,
+                    human_size: "".into(),
+                    last_modified: None,
+                }
             }));
-            items.extend(files.into_iter().map(|name| {
-                let url = get_url(&name);
-                DirectoryListingItem { name, url }
+            items.extend(files.into_iter().map(|entry| {
+                let url = get_url(&entry.name);
+                DirectoryListingItem {
+                    name: entry.name,
+                    url,
+                    size: entry.size,
+                    human_size: entry.size.map(human_size).unwrap_or_default(),
+                    last_modified: entry.last_modified,
+                }
             }));
             let basepath = &format!("/{}", base);
             let parentpath = get_parent(&base);
@@ -136,3 +177,68 @@ impl DirectoryLister {
         }
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn meta(name: &str, size: u64, last_modified: &str) -> ObjectMeta {
+        ObjectMeta {
+            name: name.into(),
+            size: Some(size),
+            last_modified: Some(last_modified.into()),
+        }
+    }
+
+    fn names(files: &[ObjectMeta]) -> Vec<&str> {
+        files.iter().map(|f| f.name.as_str()).collect()
+    }
+
+    #[test]
+    fn sort_files_test_name_asc() {
+        let mut files = vec![meta("b", 1, "2021-01-02"), meta("a", 2, "2021-01-01")];
+        sort_files(&mut files, SortKey::Name, SortOrder::Asc);
+        assert_eq!(names(&files), vec!["a", "b"]);
+    }
+
+    #[test]
+    fn sort_files_test_name_desc() {
+        let mut files = vec![meta("a", 1, "2021-01-01"), meta("b", 2, "2021-01-02")];
+        sort_files(&mut files, SortKey::Name, SortOrder::Desc);
+        assert_eq!(names(&files), vec!["b", "a"]);
+    }
+
+    #[test]
+    fn sort_files_test_size_asc() {
+        let mut files = vec![meta("big", 100, "2021-01-01"), meta("small", 1, "2021-01-01")];
+        sort_files(&mut files, SortKey::Size, SortOrder::Asc);
+        assert_eq!(names(&files), vec!["small", "big"]);
+    }
+
+    #[test]
+    fn sort_files_test_size_desc() {
+        let mut files = vec![meta("small", 1, "2021-01-01"), meta("big", 100, "2021-01-01")];
+        sort_files(&mut files, SortKey::Size, SortOrder::Desc);
+        assert_eq!(names(&files), vec!["big", "small"]);
+    }
+
+    #[test]
+    fn sort_files_test_mtime_asc() {
+        let mut files = vec![
+            meta("newer", 1, "2021-06-01"),
+            meta("older", 1, "2021-01-01"),
+        ];
+        sort_files(&mut files, SortKey::Mtime, SortOrder::Asc);
+        assert_eq!(names(&files), vec!["older", "newer"]);
+    }
+
+    #[test]
+    fn sort_files_test_mtime_desc() {
+        let mut files = vec![
+            meta("older", 1, "2021-01-01"),
+            meta("newer", 1, "2021-06-01"),
+        ];
+        sort_files(&mut files, SortKey::Mtime, SortOrder::Desc);
+        assert_eq!(names(&files), vec!["newer", "older"]);
+    }
+}
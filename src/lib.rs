@@ -0,0 +1,344 @@
+#![warn(clippy::all)]
+pub mod config;
+mod directory_listing;
+mod object_store;
+mod retry;
+mod utils;
+
+use config::FileConfig;
+use directory_listing::{DirectoryLister, SortKey, SortOrder};
+use log::error;
+use object_store::{AzureStore, GcsStore, ObjectStore, ObjectStoreError, S3Store};
+use rusoto_core::Region;
+use serde::Deserialize;
+use std::{
+    convert::Infallible, net::SocketAddr, path::PathBuf, str::FromStr, sync::Arc, time::Duration,
+};
+use structopt::StructOpt;
+use thiserror::Error;
+use warp::{
+    http::Response,
+    hyper::{Body, StatusCode},
+    path::FullPath,
+    reject::Reject,
+    Filter, Rejection,
+};
+
+const DEFAULT_LISTEN: &str = "127.0.0.1:3030";
+const DEFAULT_PRESIGN_EXPIRY_SECS: u64 = 60 * 60 * 24;
+const DEFAULT_MAX_RETRIES: u32 = 5;
+
+#[derive(Error, Debug)]
+enum RequestError {
+    #[error("encoding error")]
+    EncodingError(#[from] std::str::Utf8Error),
+    #[error("response build error")]
+    BadResponse(#[from] warp::http::Error),
+    #[error("object store error")]
+    Store(#[from] ObjectStoreError),
+}
+impl Reject for RequestError {}
+
+#[derive(Deserialize, Debug)]
+struct ListingQuery {
+    sort: Option<String>,
+    order: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy)]
+pub enum Mode {
+    Redirect,
+    Proxy,
+}
+
+impl FromStr for Mode {
+    type Err = String;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "redirect" => Ok(Mode::Redirect),
+            "proxy" => Ok(Mode::Proxy),
+            _ => Err(format!(
+                "unknown mode '{}', expected 'redirect' or 'proxy'",
+                s
+            )),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+pub enum Backend {
+    S3,
+    Azure,
+    Gcs,
+}
+
+impl FromStr for Backend {
+    type Err = String;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "s3" => Ok(Backend::S3),
+            "azure" => Ok(Backend::Azure),
+            "gcs" => Ok(Backend::Gcs),
+            _ => Err(format!(
+                "unknown backend '{}', expected 's3', 'azure', or 'gcs'",
+                s
+            )),
+        }
+    }
+}
+
+pub struct Ctx {
+    store: Box<dyn ObjectStore>,
+    lister: DirectoryLister,
+    mode: Mode,
+    max_presign_expires_in: Duration,
+}
+
+// TODO is there some way to avoid the box in the return?
+async fn request(
+    path: FullPath,
+    range: Option<String>,
+    query: ListingQuery,
+    ctx: Arc<Ctx>,
+) -> Result<Box<dyn warp::Reply>, Rejection> {
+    let pathstr = &percent_encoding::percent_decode_str(path.as_str())
+        .decode_utf8()
+        .map_err(RequestError::EncodingError)?;
+    let pathstr = pathstr
+        .strip_prefix("/")
+        .ok_or_else(|| warp::reject::not_found())?;
+    if pathstr.is_empty() || pathstr.ends_with("/") {
+        let sort = query
+            .sort
+            .and_then(|s| SortKey::from_str(&s).ok())
+            .unwrap_or_default();
+        let order = query
+            .order
+            .and_then(|s| SortOrder::from_str(&s).ok())
+            .unwrap_or_default();
+        ctx.lister
+            .directory_listing(pathstr, ctx.store.as_ref(), sort, order)
+            .await
+    } else {
+        match ctx.mode {
+            Mode::Redirect => {
+                // TODO head object before presigning? check commit history for some of that code.
+                let presigned = ctx
+                    .store
+                    .presign(pathstr, ctx.max_presign_expires_in)
+                    .await
+                    .map_err(RequestError::Store)?;
+                Ok(Box::new(warp::redirect::temporary(presigned)))
+            }
+            Mode::Proxy => proxy_object(&ctx, pathstr, range).await,
+        }
+    }
+}
+
+// Streams the object body straight through instead of redirecting, so the
+// bucket/container never has to be reachable from the client directly.
+async fn proxy_object(
+    ctx: &Ctx,
+    key: &str,
+    range: Option<String>,
+) -> Result<Box<dyn warp::Reply>, Rejection> {
+    let output = ctx
+        .store
+        .get(key, range)
+        .await
+        .map_err(RequestError::Store)?;
+    // The backend may ignore a malformed/unsatisfiable range and serve the
+    // full object instead, so the response status must reflect what was
+    // actually served (output.content_range), not what the client asked for.
+    let mut builder = Response::builder().status(if output.content_range.is_some() {
+        StatusCode::PARTIAL_CONTENT
+    } else {
+        StatusCode::OK
+    });
+    if let Some(content_type) = output.content_type {
+        builder = builder.header("content-type", content_type);
+    }
+    if let Some(content_length) = output.content_length {
+        builder = builder.header("content-length", content_length.to_string());
+    }
+    builder = builder.header(
+        "accept-ranges",
+        output.accept_ranges.unwrap_or_else(|| "bytes".into()),
+    );
+    if let Some(content_range) = output.content_range {
+        builder = builder.header("content-range", content_range);
+    }
+    if let Some(etag) = output.etag {
+        builder = builder.header("etag", etag);
+    }
+    if let Some(last_modified) = output.last_modified {
+        builder = builder.header("last-modified", last_modified);
+    }
+    Ok(Box::new(
+        builder
+            .body(Body::wrap_stream(output.body))
+            .map_err(RequestError::BadResponse)?,
+    ))
+}
+
+async fn handle_errors(e: Rejection) -> Result<impl warp::Reply, Infallible> {
+    let code;
+    let message;
+
+    if e.is_not_found() || matches!(e.find::<ObjectStoreError>(), Some(ObjectStoreError::NotFound)) {
+        code = StatusCode::NOT_FOUND;
+        message = "NOT_FOUND";
+    } else if let Some(_) = e.find::<warp::reject::MethodNotAllowed>() {
+        code = StatusCode::METHOD_NOT_ALLOWED;
+        message = "METHOD_NOT_ALLOWED";
+    } else {
+        error!("unhandled rejection: {:?}", e);
+        code = StatusCode::INTERNAL_SERVER_ERROR;
+        message = "UNHANDLED_ERROR";
+    }
+
+    Ok(warp::reply::with_status(message, code))
+}
+
+#[derive(StructOpt, Debug, Default)]
+#[structopt(name = "bitte")]
+pub struct Opt {
+    #[structopt(long)]
+    pub bucket: Option<String>,
+
+    #[structopt(long)]
+    pub region: Option<String>,
+
+    #[structopt(long)]
+    pub endpoint: Option<String>,
+
+    #[structopt(long)]
+    pub mode: Option<Mode>,
+
+    #[structopt(long)]
+    pub backend: Option<Backend>,
+
+    #[structopt(long)]
+    pub max_retries: Option<u32>,
+
+    #[structopt(long)]
+    pub listen: Option<String>,
+
+    #[structopt(long)]
+    pub presign_expiry_secs: Option<u64>,
+
+    #[structopt(long)]
+    pub config: Option<PathBuf>,
+}
+
+fn resolve_region(region: &Option<String>, endpoint: &Option<String>) -> Region {
+    if let Some(endpoint) = endpoint.clone() {
+        Region::Custom {
+            name: region.clone().unwrap_or_else(|| "custom".into()),
+            endpoint,
+        }
+    } else if let Some(region) = region {
+        Region::from_str(region).expect("bad region provided")
+    } else {
+        Region::default()
+    }
+}
+
+pub fn resolve_listen_address(opt: &Opt, file: &FileConfig) -> SocketAddr {
+    let listen = opt
+        .listen
+        .clone()
+        .or_else(|| file.listen.clone())
+        .unwrap_or_else(|| DEFAULT_LISTEN.to_string());
+    listen.parse().expect("invalid listen address")
+}
+
+pub async fn build_ctx(opt: &Opt, file: &FileConfig) -> Arc<Ctx> {
+    let bucket = opt
+        .bucket
+        .clone()
+        .or_else(|| file.bucket.clone())
+        .expect("bucket must be set via --bucket or the config file");
+    let region = resolve_region(
+        &opt.region.clone().or_else(|| file.region.clone()),
+        &opt.endpoint.clone().or_else(|| file.endpoint.clone()),
+    );
+    let mode = opt
+        .mode
+        .or_else(|| {
+            file.mode.as_deref().map(|s| {
+                Mode::from_str(s).unwrap_or_else(|e| panic!("invalid config file value: {}", e))
+            })
+        })
+        .unwrap_or(Mode::Redirect);
+    let backend = opt
+        .backend
+        .or_else(|| {
+            file.backend.as_deref().map(|s| {
+                Backend::from_str(s).unwrap_or_else(|e| panic!("invalid config file value: {}", e))
+            })
+        })
+        .unwrap_or(Backend::S3);
+    let max_retries = opt
+        .max_retries
+        .or(file.max_retries)
+        .unwrap_or(DEFAULT_MAX_RETRIES);
+    let presign_expiry_secs = opt
+        .presign_expiry_secs
+        .or(file.presign_expiry_secs)
+        .unwrap_or(DEFAULT_PRESIGN_EXPIRY_SECS);
+    let store: Box<dyn ObjectStore> = match backend {
+        Backend::S3 => Box::new(
+            S3Store::new(bucket, region, max_retries)
+                .await
+                .expect("failed to initialize s3 backend"),
+        ),
+        Backend::Azure => Box::new(
+            AzureStore::new(bucket)
+                .await
+                .expect("failed to initialize azure backend"),
+        ),
+        Backend::Gcs => Box::new(
+            GcsStore::new(bucket)
+                .await
+                .expect("failed to initialize gcs backend"),
+        ),
+    };
+    Arc::new(Ctx {
+        store,
+        lister: DirectoryLister::new(),
+        mode,
+        max_presign_expires_in: Duration::from_secs(presign_expiry_secs),
+    })
+}
+
+pub fn build_route(
+    ctx: Arc<Ctx>,
+) -> impl Filter<Extract = (impl warp::Reply,), Error = Infallible> + Clone {
+    warp::path::full()
+        .and(warp::header::optional::<String>("range"))
+        .and(warp::query::<ListingQuery>())
+        .and_then(move |path: FullPath, range: Option<String>, query: ListingQuery| {
+            request(path, range, query, ctx.clone())
+        })
+        .recover(handle_errors)
+}
+
+pub async fn run() {
+    env_logger::Builder::from_env(env_logger::Env::default().default_filter_or("info"))
+        .target(env_logger::Target::Stdout)
+        .format_timestamp(None)
+        .format_module_path(false)
+        .init();
+    let opt = Opt::from_args();
+    let file_config = match &opt.config {
+        Some(path) => FileConfig::load(path).expect("failed to load config file"),
+        None => FileConfig::default(),
+    };
+    let listen_address = resolve_listen_address(&opt, &file_config);
+    let ctx = build_ctx(&opt, &file_config).await;
+    let route = build_route(ctx);
+
+    // TODO access logging
+    warp::serve(route).run(listen_address).await;
+}
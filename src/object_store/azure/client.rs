@@ -0,0 +1,152 @@
+use super::credential::AzureCredential;
+use crate::object_store::{BoxByteStream, ObjectBody, ObjectMeta, ObjectStore, ObjectStoreError};
+use async_trait::async_trait;
+use azure_storage_blobs::prelude::*;
+use futures::stream::StreamExt;
+use std::{str::FromStr, time::Duration};
+use warp::hyper::Uri;
+
+pub struct AzureStore {
+    container: ContainerClient,
+}
+
+impl AzureStore {
+    pub async fn new(container_name: String) -> Result<Self, ObjectStoreError> {
+        let credential = AzureCredential::from_env()?;
+        let service =
+            BlobServiceClient::new(credential.account.clone(), credential.storage_credentials());
+        Ok(AzureStore {
+            container: service.container_client(container_name),
+        })
+    }
+}
+
+// Parses a single `bytes=start-end` Range header value into an inclusive
+// `(start, end)` pair. Multi-range and open-ended (`bytes=500-`) requests
+// aren't supported here; the S3 backend instead forwards the header as-is.
+fn parse_byte_range(header: &str) -> Option<(u64, u64)> {
+    let spec = header.strip_prefix("bytes=")?;
+    let (start, end) = spec.split_once('-')?;
+    let start: u64 = start.parse().ok()?;
+    let end: u64 = end.parse().ok()?;
+    if end < start {
+        return None;
+    }
+    Some((start, end))
+}
+
+#[async_trait]
+impl ObjectStore for AzureStore {
+    async fn list(&self, prefix: &str) -> Result<(Vec<String>, Vec<ObjectMeta>), ObjectStoreError> {
+        let mut dirs = vec![];
+        let mut files = vec![];
+        let mut pages = self
+            .container
+            .list_blobs()
+            .delimiter(String::from("/"))
+            .prefix(prefix.to_string())
+            .into_stream();
+        while let Some(page) = pages.next().await {
+            let page = page.map_err(ObjectStoreError::backend)?;
+            dirs.extend(
+                page.blobs
+                    .blob_prefix
+                    .into_iter()
+                    .filter_map(|p| p.name.strip_prefix(prefix).map(Into::into)),
+            );
+            files.extend(page.blobs.blobs.into_iter().filter_map(|b| {
+                let name = b.name.strip_prefix(prefix)?.to_string();
+                Some(ObjectMeta {
+                    name,
+                    size: Some(b.properties.content_length),
+                    last_modified: Some(b.properties.last_modified.to_string()),
+                })
+            }));
+        }
+        Ok((dirs, files))
+    }
+
+    async fn get(&self, key: &str, range: Option<String>) -> Result<ObjectBody, ObjectStoreError> {
+        let blob = self.container.blob_client(key);
+        let properties = blob
+            .get_properties()
+            .await
+            .map_err(ObjectStoreError::backend)?
+            .blob
+            .properties;
+        let mut builder = blob.get();
+        let mut served_range = None;
+        if let Some(range) = &range {
+            let (start, end) = parse_byte_range(range).ok_or_else(|| {
+                ObjectStoreError::config(format!("unsupported Range header: {}", range))
+            })?;
+            let exclusive_end = end.checked_add(1).ok_or_else(|| {
+                ObjectStoreError::config(format!("unsatisfiable Range header: {}", range))
+            })?;
+            builder = builder.range(start..exclusive_end);
+            served_range = Some((start, end));
+        }
+        let body: BoxByteStream = Box::pin(builder.into_stream().map(|page| {
+            page.map(|p| p.data)
+                .map_err(|e| std::io::Error::new(std::io::ErrorKind::Other, e.to_string()))
+        }));
+        let content_range = served_range
+            .map(|(start, end)| format!("bytes {}-{}/{}", start, end, properties.content_length));
+        Ok(ObjectBody {
+            body,
+            content_type: Some(properties.content_type),
+            content_length: Some(properties.content_length),
+            content_range,
+            accept_ranges: Some("bytes".into()),
+            etag: Some(properties.etag),
+            last_modified: Some(properties.last_modified.to_string()),
+        })
+    }
+
+    async fn presign(&self, key: &str, max_expires_in: Duration) -> Result<Uri, ObjectStoreError> {
+        let blob = self.container.blob_client(key);
+        let permissions = BlobSasPermissions {
+            read: true,
+            ..Default::default()
+        };
+        let expiry = time::OffsetDateTime::now_utc() + max_expires_in;
+        let sas = blob
+            .shared_access_signature(permissions, expiry)
+            .await
+            .map_err(ObjectStoreError::backend)?;
+        let url = blob
+            .generate_signed_blob_url(&sas)
+            .map_err(ObjectStoreError::backend)?;
+        Uri::from_str(url.as_str()).map_err(ObjectStoreError::backend)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn parse_byte_range_test_in_range() {
+        assert_eq!(parse_byte_range("bytes=0-499"), Some((0, 499)));
+    }
+
+    #[test]
+    fn parse_byte_range_test_end_before_start_rejected() {
+        assert_eq!(parse_byte_range("bytes=500-0"), None);
+    }
+
+    #[test]
+    fn parse_byte_range_test_max_end_does_not_panic() {
+        // Regression test for an overflow in the caller that used to compute
+        // `end + 1` directly on this value; parsing itself must not panic.
+        assert_eq!(
+            parse_byte_range("bytes=0-18446744073709551615"),
+            Some((0, u64::MAX))
+        );
+    }
+
+    #[test]
+    fn parse_byte_range_test_malformed() {
+        assert_eq!(parse_byte_range("not-a-range"), None);
+    }
+}
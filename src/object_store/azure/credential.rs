@@ -0,0 +1,27 @@
+use crate::object_store::ObjectStoreError;
+use azure_storage::StorageCredentials;
+
+/// Loads an Azure storage account key the same way the `AZURE_STORAGE_ACCOUNT`
+/// / `AZURE_STORAGE_ACCESS_KEY` environment variables are read elsewhere in
+/// the Azure SDK ecosystem, so operators can reuse existing tooling/env setup.
+pub struct AzureCredential {
+    pub account: String,
+    pub access_key: String,
+}
+
+impl AzureCredential {
+    pub fn from_env() -> Result<Self, ObjectStoreError> {
+        let account = std::env::var("AZURE_STORAGE_ACCOUNT")
+            .map_err(|_| ObjectStoreError::config("AZURE_STORAGE_ACCOUNT not set"))?;
+        let access_key = std::env::var("AZURE_STORAGE_ACCESS_KEY")
+            .map_err(|_| ObjectStoreError::config("AZURE_STORAGE_ACCESS_KEY not set"))?;
+        Ok(AzureCredential {
+            account,
+            access_key,
+        })
+    }
+
+    pub fn storage_credentials(&self) -> StorageCredentials {
+        StorageCredentials::access_key(self.account.clone(), self.access_key.clone())
+    }
+}
@@ -0,0 +1,181 @@
+use super::credential::load_client_config;
+use crate::object_store::{BoxByteStream, ObjectBody, ObjectMeta, ObjectStore, ObjectStoreError};
+use async_trait::async_trait;
+use futures::stream;
+use google_cloud_storage::{
+    client::Client,
+    http::objects::{download::Range as GcsRange, get::GetObjectRequest, list::ListObjectsRequest},
+    sign::{SignedURLMethod, SignedURLOptions},
+};
+use std::{str::FromStr, time::Duration};
+use warp::hyper::Uri;
+
+pub struct GcsStore {
+    client: Client,
+    bucket: String,
+}
+
+impl GcsStore {
+    pub async fn new(bucket: String) -> Result<Self, ObjectStoreError> {
+        let config = load_client_config().await?;
+        Ok(GcsStore {
+            client: Client::new(config),
+            bucket,
+        })
+    }
+}
+
+// Parses a single `bytes=start-end` Range header value into an inclusive
+// `(start, end)` pair, same scope as the Azure backend: no multi-range or
+// open-ended requests.
+fn parse_byte_range(header: &str) -> Option<(u64, u64)> {
+    let spec = header.strip_prefix("bytes=")?;
+    let (start, end) = spec.split_once('-')?;
+    let start: u64 = start.parse().ok()?;
+    let end: u64 = end.parse().ok()?;
+    if end < start {
+        return None;
+    }
+    Some((start, end))
+}
+
+#[async_trait]
+impl ObjectStore for GcsStore {
+    async fn list(&self, prefix: &str) -> Result<(Vec<String>, Vec<ObjectMeta>), ObjectStoreError> {
+        let mut dirs = vec![];
+        let mut files = vec![];
+        let mut page_token = None;
+        loop {
+            let resp = self
+                .client
+                .list_objects(&ListObjectsRequest {
+                    bucket: self.bucket.clone(),
+                    prefix: Some(prefix.to_string()),
+                    delimiter: Some("/".to_string()),
+                    page_token: page_token.take(),
+                    ..Default::default()
+                })
+                .await
+                .map_err(ObjectStoreError::backend)?;
+            dirs.extend(
+                resp.prefixes
+                    .unwrap_or_default()
+                    .into_iter()
+                    .filter_map(|p| p.strip_prefix(prefix).map(Into::into)),
+            );
+            files.extend(resp.items.unwrap_or_default().into_iter().filter_map(|o| {
+                let name = o.name.strip_prefix(prefix)?.to_string();
+                Some(ObjectMeta {
+                    name,
+                    size: o.size.parse::<u64>().ok(),
+                    last_modified: Some(o.updated),
+                })
+            }));
+            page_token = resp.next_page_token;
+            if page_token.is_none() {
+                break;
+            }
+        }
+        Ok((dirs, files))
+    }
+
+    async fn get(&self, key: &str, range: Option<String>) -> Result<ObjectBody, ObjectStoreError> {
+        let meta = self
+            .client
+            .get_object(&GetObjectRequest {
+                bucket: self.bucket.clone(),
+                object: key.to_string(),
+                ..Default::default()
+            })
+            .await
+            .map_err(ObjectStoreError::backend)?;
+        let parsed_range = range
+            .as_deref()
+            .map(|r| {
+                parse_byte_range(r).ok_or_else(|| {
+                    ObjectStoreError::config(format!("unsupported Range header: {}", r))
+                })
+            })
+            .transpose()?;
+        let gcs_range = parsed_range
+            .map(|(start, end)| GcsRange(Some(start), Some(end)))
+            .unwrap_or_default();
+        // TODO this buffers the whole (ranged) object in memory — the
+        // google-cloud-storage client doesn't expose a streaming download, so
+        // this backend can't match the S3 backend's constant-memory proxying.
+        let data = self
+            .client
+            .download_object(
+                &GetObjectRequest {
+                    bucket: self.bucket.clone(),
+                    object: key.to_string(),
+                    ..Default::default()
+                },
+                &gcs_range,
+            )
+            .await
+            .map_err(ObjectStoreError::backend)?;
+        let content_range = parsed_range
+            .map(|(start, end)| format!("bytes {}-{}/{}", start, end, meta.size));
+        let body: BoxByteStream =
+            Box::pin(stream::once(
+                async move { Ok(bytes::Bytes::from(data)) },
+            ));
+        Ok(ObjectBody {
+            body,
+            content_type: meta.content_type,
+            content_length: meta.size.parse::<u64>().ok(),
+            content_range,
+            accept_ranges: Some("bytes".into()),
+            etag: Some(meta.etag),
+            last_modified: Some(meta.updated),
+        })
+    }
+
+    async fn presign(&self, key: &str, max_expires_in: Duration) -> Result<Uri, ObjectStoreError> {
+        let url = self
+            .client
+            .signed_url(
+                &self.bucket,
+                key,
+                None,
+                None,
+                SignedURLOptions {
+                    method: SignedURLMethod::GET,
+                    expires: max_expires_in,
+                    ..Default::default()
+                },
+            )
+            .await
+            .map_err(ObjectStoreError::backend)?;
+        Uri::from_str(&url).map_err(ObjectStoreError::backend)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn parse_byte_range_test_in_range() {
+        assert_eq!(parse_byte_range("bytes=0-499"), Some((0, 499)));
+    }
+
+    #[test]
+    fn parse_byte_range_test_end_before_start_rejected() {
+        assert_eq!(parse_byte_range("bytes=500-0"), None);
+    }
+
+    #[test]
+    fn parse_byte_range_test_max_end_does_not_panic() {
+        assert_eq!(
+            parse_byte_range("bytes=0-18446744073709551615"),
+            Some((0, u64::MAX))
+        );
+    }
+
+    #[test]
+    fn parse_byte_range_test_malformed() {
+        assert_eq!(parse_byte_range("not-a-range"), None);
+    }
+}
@@ -0,0 +1,12 @@
+use crate::object_store::ObjectStoreError;
+use google_cloud_storage::client::ClientConfig;
+
+/// Authenticates the same way `gcloud`/the Google client libraries do: via
+/// `GOOGLE_APPLICATION_CREDENTIALS` (or the ambient metadata server when
+/// running on GCP), so no bitte-specific credential plumbing is needed.
+pub async fn load_client_config() -> Result<ClientConfig, ObjectStoreError> {
+    ClientConfig::default()
+        .with_auth()
+        .await
+        .map_err(ObjectStoreError::backend)
+}
@@ -0,0 +1,4 @@
+mod client;
+mod credential;
+
+pub use client::GcsStore;
@@ -0,0 +1,76 @@
+//! Backend-agnostic access to whatever bucket/container is serving the tree
+//! being browsed. `DirectoryLister` and `request()` only ever talk to this
+//! trait, so adding a new backend never touches the warp routing or the
+//! directory-listing template.
+
+mod azure;
+mod gcs;
+mod s3;
+
+pub use azure::AzureStore;
+pub use gcs::GcsStore;
+pub use s3::S3Store;
+
+use async_trait::async_trait;
+use bytes::Bytes;
+use futures::stream::Stream;
+use std::{pin::Pin, time::Duration};
+use thiserror::Error;
+use warp::hyper::Uri;
+
+pub type BoxByteStream = Pin<Box<dyn Stream<Item = Result<Bytes, std::io::Error>> + Send>>;
+
+#[derive(Error, Debug)]
+pub enum ObjectStoreError {
+    #[error("object not found")]
+    NotFound,
+    #[error("{0}")]
+    Config(String),
+    #[error("backend error")]
+    Backend(#[source] Box<dyn std::error::Error + Send + Sync>),
+}
+
+impl ObjectStoreError {
+    pub fn backend(e: impl std::error::Error + Send + Sync + 'static) -> Self {
+        ObjectStoreError::Backend(Box::new(e))
+    }
+
+    pub fn config(msg: impl Into<String>) -> Self {
+        ObjectStoreError::Config(msg.into())
+    }
+}
+
+impl warp::reject::Reject for ObjectStoreError {}
+
+/// A single file entry returned from a [`ObjectStore::list`] call.
+pub struct ObjectMeta {
+    pub name: String,
+    pub size: Option<u64>,
+    pub last_modified: Option<String>,
+}
+
+/// The body and headers for a single object fetched with [`ObjectStore::get`].
+pub struct ObjectBody {
+    pub body: BoxByteStream,
+    pub content_type: Option<String>,
+    pub content_length: Option<u64>,
+    pub content_range: Option<String>,
+    pub accept_ranges: Option<String>,
+    pub etag: Option<String>,
+    pub last_modified: Option<String>,
+}
+
+#[async_trait]
+pub trait ObjectStore: Send + Sync {
+    /// Lists immediate children of `prefix` (which is empty or ends with
+    /// `/`), split into sub-"directories" and files the way S3's
+    /// delimiter-based listing does.
+    async fn list(&self, prefix: &str) -> Result<(Vec<String>, Vec<ObjectMeta>), ObjectStoreError>;
+
+    /// Fetches `key`, optionally honoring an HTTP `Range` header value.
+    async fn get(&self, key: &str, range: Option<String>) -> Result<ObjectBody, ObjectStoreError>;
+
+    /// Builds a temporary public URL for `key`, valid for at most
+    /// `max_expires_in`.
+    async fn presign(&self, key: &str, max_expires_in: Duration) -> Result<Uri, ObjectStoreError>;
+}
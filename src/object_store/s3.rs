@@ -0,0 +1,222 @@
+use super::{BoxByteStream, ObjectBody, ObjectMeta, ObjectStore, ObjectStoreError};
+use crate::retry::{is_transient_rusoto_error, retry, RetryConfig};
+use arc_swap::ArcSwap;
+use async_trait::async_trait;
+use chrono::{DateTime, Duration as ChronoDuration, Utc};
+use log::warn;
+use rusoto_core::{
+    credential::{AwsCredentials, DefaultCredentialsProvider, ProvideAwsCredentials},
+    Region,
+};
+use rusoto_s3::{
+    util::{PreSignedRequest, PreSignedRequestOption},
+    GetObjectRequest, ListObjectsV2Request, S3Client, S3,
+};
+use std::{str::FromStr, sync::Arc, time::Duration};
+use warp::hyper::Uri;
+
+// Credentials are refreshed once they're within this long of expiring, so a
+// presign never starts from an already-stale (or about-to-expire) credential.
+const CREDENTIAL_REFRESH_WINDOW_SECS: i64 = 5 * 60;
+
+// Whether credentials expiring at `expires_at` are close enough to `now` to
+// need a refresh. Split out from `current_credentials` so the decision can
+// be unit-tested without a real `DefaultCredentialsProvider`.
+fn needs_refresh(now: DateTime<Utc>, expires_at: DateTime<Utc>) -> bool {
+    now + ChronoDuration::seconds(CREDENTIAL_REFRESH_WINDOW_SECS) >= expires_at
+}
+
+pub struct S3Store {
+    s3: S3Client,
+    bucket: String,
+    region: Region,
+    credentials_provider: DefaultCredentialsProvider,
+    credentials: ArcSwap<AwsCredentials>,
+    retry_config: RetryConfig,
+}
+
+impl S3Store {
+    pub async fn new(
+        bucket: String,
+        region: Region,
+        max_retries: u32,
+    ) -> Result<Self, ObjectStoreError> {
+        let s3 = S3Client::new(region.clone());
+        let credentials_provider =
+            DefaultCredentialsProvider::new().map_err(ObjectStoreError::backend)?;
+        let credentials = credentials_provider
+            .credentials()
+            .await
+            .map_err(ObjectStoreError::backend)?;
+        Ok(S3Store {
+            s3,
+            bucket,
+            region,
+            credentials_provider,
+            credentials: ArcSwap::from_pointee(credentials),
+            retry_config: RetryConfig::new(max_retries),
+        })
+    }
+
+    // Returns the current credentials, refreshing and swapping in a fresh
+    // set first if the cached ones are close to expiring.
+    async fn current_credentials(&self) -> Result<Arc<AwsCredentials>, ObjectStoreError> {
+        let current = self.credentials.load_full();
+        let needs_refresh = current
+            .expires_at()
+            .map(|expires_at| needs_refresh(Utc::now(), expires_at))
+            .unwrap_or(false);
+        if needs_refresh {
+            let fresh = self
+                .credentials_provider
+                .credentials()
+                .await
+                .map_err(ObjectStoreError::backend)?;
+            self.credentials.store(Arc::new(fresh));
+            Ok(self.credentials.load_full())
+        } else {
+            Ok(current)
+        }
+    }
+}
+
+#[async_trait]
+impl ObjectStore for S3Store {
+    async fn list(&self, prefix: &str) -> Result<(Vec<String>, Vec<ObjectMeta>), ObjectStoreError> {
+        let mut dirs: Vec<String> = vec![];
+        let mut files: Vec<ObjectMeta> = vec![];
+        let mut continuation_token = None;
+        loop {
+            let req = ListObjectsV2Request {
+                bucket: self.bucket.clone(),
+                prefix: Some(prefix.into()),
+                delimiter: Some("/".into()),
+                continuation_token: continuation_token.take(),
+                ..Default::default()
+            };
+            let list = retry(&self.retry_config, is_transient_rusoto_error, || {
+                self.s3.list_objects_v2(req.clone())
+            })
+            .await
+            .map_err(ObjectStoreError::backend)?;
+            continuation_token = list.next_continuation_token;
+            if let Some(common) = list.common_prefixes {
+                dirs.extend(common.into_iter().filter_map(|c| {
+                    let p = c.prefix.or_else(|| {
+                        warn!("none in s3 listing common_prefixes");
+                        None
+                    })?;
+                    p.strip_prefix(prefix).map(Into::into).or_else(|| {
+                        warn!("common prefix without expected prefix found ({})", p);
+                        None
+                    })
+                }));
+            }
+            if let Some(contents) = list.contents {
+                files.extend(contents.into_iter().filter_map(|c| -> Option<ObjectMeta> {
+                    let key = c.key.or_else(|| {
+                        warn!("none key in s3 listing contents");
+                        None
+                    })?;
+                    if key.ends_with('/') {
+                        warn!("key ending with / found ({})", key);
+                        return None;
+                    }
+                    let name = key.strip_prefix(prefix).map(Into::into).or_else(|| {
+                        warn!("key without expected prefix found ({})", key);
+                        None
+                    })?;
+                    Some(ObjectMeta {
+                        name,
+                        size: c.size.map(|s| s as u64),
+                        last_modified: c.last_modified,
+                    })
+                }));
+            }
+            if continuation_token.is_none() {
+                break;
+            }
+        }
+        Ok((dirs, files))
+    }
+
+    async fn get(&self, key: &str, range: Option<String>) -> Result<ObjectBody, ObjectStoreError> {
+        let req = GetObjectRequest {
+            bucket: self.bucket.clone(),
+            key: key.into(),
+            range,
+            ..Default::default()
+        };
+        let output = retry(&self.retry_config, is_transient_rusoto_error, || {
+            self.s3.get_object(req.clone())
+        })
+        .await
+        .map_err(ObjectStoreError::backend)?;
+        let body: BoxByteStream = Box::pin(output.body.ok_or(ObjectStoreError::NotFound)?);
+        Ok(ObjectBody {
+            body,
+            content_type: output.content_type,
+            content_length: output.content_length.map(|l| l as u64),
+            content_range: output.content_range,
+            accept_ranges: output.accept_ranges,
+            etag: output.e_tag,
+            last_modified: output.last_modified,
+        })
+    }
+
+    async fn presign(&self, key: &str, max_expires_in: Duration) -> Result<Uri, ObjectStoreError> {
+        let credentials = self.current_credentials().await?;
+        // A presigned URL stops validating once its signing credential
+        // expires, so never hand out one that outlives the credential.
+        let expires_in = credentials
+            .expires_at()
+            .and_then(|expires_at| (expires_at - Utc::now()).to_std().ok())
+            .map(|credential_ttl| std::cmp::min(credential_ttl, max_expires_in))
+            .unwrap_or(max_expires_in);
+        let req = GetObjectRequest {
+            bucket: self.bucket.clone(),
+            key: key.into(),
+            ..Default::default()
+        };
+        let presigned = req.get_presigned_url(
+            &self.region,
+            &credentials,
+            &PreSignedRequestOption { expires_in },
+        );
+        Uri::from_str(&presigned).map_err(ObjectStoreError::backend)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn needs_refresh_test_well_before_expiry() {
+        let now = Utc::now();
+        let expires_at = now + ChronoDuration::hours(1);
+        assert!(!needs_refresh(now, expires_at));
+    }
+
+    #[test]
+    fn needs_refresh_test_inside_refresh_window() {
+        let now = Utc::now();
+        let expires_at = now + ChronoDuration::seconds(CREDENTIAL_REFRESH_WINDOW_SECS - 1);
+        assert!(needs_refresh(now, expires_at));
+    }
+
+    #[test]
+    fn needs_refresh_test_already_expired() {
+        let now = Utc::now();
+        let expires_at = now - ChronoDuration::seconds(1);
+        assert!(needs_refresh(now, expires_at));
+    }
+
+    #[test]
+    fn needs_refresh_test_exactly_at_window_boundary() {
+        let now = Utc::now();
+        let expires_at = now + ChronoDuration::seconds(CREDENTIAL_REFRESH_WINDOW_SECS);
+        assert!(needs_refresh(now, expires_at));
+    }
+}
+
@@ -0,0 +1,169 @@
+use rand::Rng;
+use rusoto_core::RusotoError;
+use std::{future::Future, time::Duration};
+
+const BASE_DELAY: Duration = Duration::from_millis(50);
+const MAX_DELAY: Duration = Duration::from_secs(10);
+
+#[derive(Debug, Clone, Copy)]
+pub struct RetryConfig {
+    pub max_attempts: u32,
+}
+
+impl RetryConfig {
+    pub fn new(max_attempts: u32) -> Self {
+        RetryConfig { max_attempts }
+    }
+}
+
+/// Retries `op` with exponential backoff (doubling from [`BASE_DELAY`] up to
+/// [`MAX_DELAY`], plus jitter) as long as `is_transient` says the error is
+/// worth retrying and the attempt budget isn't exhausted.
+pub async fn retry<T, E, F, Fut>(
+    config: &RetryConfig,
+    is_transient: impl Fn(&E) -> bool,
+    mut op: F,
+) -> Result<T, E>
+where
+    F: FnMut() -> Fut,
+    Fut: Future<Output = Result<T, E>>,
+{
+    let mut attempt = 1;
+    let mut delay = BASE_DELAY;
+    loop {
+        match op().await {
+            Ok(value) => return Ok(value),
+            Err(err) if attempt < config.max_attempts && is_transient(&err) => {
+                let jitter_range = 0..(delay.as_millis() as u64 + 1);
+                let jitter = Duration::from_millis(rand::thread_rng().gen_range(jitter_range));
+                tokio::time::sleep(delay + jitter).await;
+                delay = std::cmp::min(delay * 2, MAX_DELAY);
+                attempt += 1;
+            }
+            Err(err) => return Err(err),
+        }
+    }
+}
+
+/// Whether a rusoto S3 error looks like a transient condition (throttling,
+/// a 5xx, or a connection-level failure) rather than something retrying
+/// won't fix, like `NoSuchKey` or a credentials/auth error.
+pub fn is_transient_rusoto_error<E>(err: &RusotoError<E>) -> bool {
+    match err {
+        RusotoError::HttpDispatch(_) => true,
+        RusotoError::Unknown(response) => {
+            matches!(response.status.as_u16(), 429 | 500 | 502 | 503 | 504)
+        }
+        RusotoError::Service(_)
+        | RusotoError::Validation(_)
+        | RusotoError::Credentials(_)
+        | RusotoError::ParseError(_)
+        | RusotoError::Blocking => false,
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use rusoto_core::{credential::CredentialsError, request::HttpDispatchError, request::BufferedHttpResponse};
+    use std::sync::atomic::{AtomicU32, Ordering};
+    use warp::hyper::{HeaderMap, StatusCode};
+
+    fn unknown(status: u16) -> RusotoError<String> {
+        RusotoError::Unknown(BufferedHttpResponse {
+            status: StatusCode::from_u16(status).unwrap(),
+            body: Default::default(),
+            headers: HeaderMap::new(),
+        })
+    }
+
+    #[test]
+    fn is_transient_rusoto_error_test_http_dispatch() {
+        assert!(is_transient_rusoto_error(&RusotoError::<String>::HttpDispatch(
+            HttpDispatchError::new("connection reset".into())
+        )));
+    }
+
+    #[test]
+    fn is_transient_rusoto_error_test_unknown_throttled() {
+        assert!(is_transient_rusoto_error(&unknown(429)));
+    }
+
+    #[test]
+    fn is_transient_rusoto_error_test_unknown_server_error() {
+        assert!(is_transient_rusoto_error(&unknown(503)));
+    }
+
+    #[test]
+    fn is_transient_rusoto_error_test_unknown_not_found() {
+        assert!(!is_transient_rusoto_error(&unknown(404)));
+    }
+
+    #[test]
+    fn is_transient_rusoto_error_test_service() {
+        assert!(!is_transient_rusoto_error(&RusotoError::Service(
+            "NoSuchKey".to_string()
+        )));
+    }
+
+    #[test]
+    fn is_transient_rusoto_error_test_credentials() {
+        assert!(!is_transient_rusoto_error(&RusotoError::<String>::Credentials(
+            CredentialsError::new("no credentials found")
+        )));
+    }
+
+    #[tokio::test]
+    async fn retry_test_succeeds_without_retrying() {
+        let attempts = AtomicU32::new(0);
+        let result: Result<u32, ()> = retry(&RetryConfig::new(3), |_| true, || {
+            attempts.fetch_add(1, Ordering::SeqCst);
+            async { Ok(42) }
+        })
+        .await;
+        assert_eq!(result, Ok(42));
+        assert_eq!(attempts.load(Ordering::SeqCst), 1);
+    }
+
+    #[tokio::test]
+    async fn retry_test_retries_transient_errors_up_to_max_attempts() {
+        let attempts = AtomicU32::new(0);
+        let result: Result<(), &str> = retry(&RetryConfig::new(3), |_| true, || {
+            attempts.fetch_add(1, Ordering::SeqCst);
+            async { Err("still failing") }
+        })
+        .await;
+        assert_eq!(result, Err("still failing"));
+        assert_eq!(attempts.load(Ordering::SeqCst), 3);
+    }
+
+    #[tokio::test]
+    async fn retry_test_stops_immediately_on_non_transient_error() {
+        let attempts = AtomicU32::new(0);
+        let result: Result<(), &str> = retry(&RetryConfig::new(5), |_| false, || {
+            attempts.fetch_add(1, Ordering::SeqCst);
+            async { Err("permanent") }
+        })
+        .await;
+        assert_eq!(result, Err("permanent"));
+        assert_eq!(attempts.load(Ordering::SeqCst), 1);
+    }
+
+    #[tokio::test]
+    async fn retry_test_succeeds_after_a_transient_failure() {
+        let attempts = AtomicU32::new(0);
+        let result: Result<u32, &str> = retry(&RetryConfig::new(3), |_| true, || {
+            let attempt = attempts.fetch_add(1, Ordering::SeqCst);
+            async move {
+                if attempt == 0 {
+                    Err("transient")
+                } else {
+                    Ok(7)
+                }
+            }
+        })
+        .await;
+        assert_eq!(result, Ok(7));
+        assert_eq!(attempts.load(Ordering::SeqCst), 2);
+    }
+}
@@ -25,6 +25,22 @@ pub fn url_encode(path: &str) -> String {
     percent_encoding::utf8_percent_encode(path, PATH_SET).to_string()
 }
 
+const SIZE_UNITS: [&str; 5] = ["B", "KiB", "MiB", "GiB", "TiB"];
+
+pub fn human_size(bytes: u64) -> String {
+    let mut size = bytes as f64;
+    let mut unit = 0;
+    while size >= 1024.0 && unit < SIZE_UNITS.len() - 1 {
+        size /= 1024.0;
+        unit += 1;
+    }
+    if unit == 0 {
+        format!("{} {}", bytes, SIZE_UNITS[unit])
+    } else {
+        format!("{:.1} {}", size, SIZE_UNITS[unit])
+    }
+}
+
 #[cfg(test)]
 mod test {
     use super::*;
@@ -54,4 +70,24 @@ mod test {
     fn get_parent_test_4() {
         let _ = get_parent("asdf");
     }
+
+    #[test]
+    fn human_size_test_0() {
+        assert_eq!(human_size(0), "0 B");
+    }
+
+    #[test]
+    fn human_size_test_1() {
+        assert_eq!(human_size(1023), "1023 B");
+    }
+
+    #[test]
+    fn human_size_test_2() {
+        assert_eq!(human_size(1024), "1.0 KiB");
+    }
+
+    #[test]
+    fn human_size_test_3() {
+        assert_eq!(human_size(5 * 1024 * 1024), "5.0 MiB");
+    }
 }
@@ -0,0 +1,155 @@
+use bitte::{build_ctx, build_route, config::FileConfig, resolve_listen_address, Opt};
+use std::io::Write;
+use wiremock::{
+    matchers::{method, path},
+    Mock, MockServer, ResponseTemplate,
+};
+
+const LIST_OBJECTS_XML: &str = r#"<?xml version="1.0" encoding="UTF-8"?>
+<ListBucketResult xmlns="http://s3.amazonaws.com/doc/2006-03-01/">
+    <Name>test-bucket</Name>
+    <Prefix></Prefix>
+    <KeyCount>1</KeyCount>
+    <MaxKeys>1000</MaxKeys>
+    <IsTruncated>false</IsTruncated>
+    <Contents>
+        <Key>hello.txt</Key>
+        <LastModified>2021-01-01T00:00:00.000Z</LastModified>
+        <Size>5</Size>
+        <StorageClass>STANDARD</StorageClass>
+    </Contents>
+</ListBucketResult>"#;
+
+async fn opt_for(mock_server: &MockServer, config_path: Option<std::path::PathBuf>) -> Opt {
+    Opt {
+        bucket: Some("test-bucket".into()),
+        region: Some("us-east-1".into()),
+        endpoint: Some(mock_server.uri()),
+        mode: Some(bitte::Mode::Proxy),
+        backend: None,
+        max_retries: Some(1),
+        listen: None,
+        presign_expiry_secs: None,
+        config: config_path,
+    }
+}
+
+#[tokio::test]
+async fn lists_directory_contents() {
+    let mock_server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/test-bucket"))
+        .respond_with(ResponseTemplate::new(200).set_body_string(LIST_OBJECTS_XML))
+        .mount(&mock_server)
+        .await;
+
+    let opt = opt_for(&mock_server, None).await;
+    let file_config = FileConfig::default();
+    let ctx = build_ctx(&opt, &file_config).await;
+    let route = build_route(ctx);
+
+    let resp = warp::test::request().path("/").reply(&route).await;
+    assert_eq!(resp.status(), 200);
+    let body = String::from_utf8_lossy(resp.body());
+    assert!(body.contains("hello.txt"));
+}
+
+const LIST_OBJECTS_XML_TWO_ENTRIES: &str = r#"<?xml version="1.0" encoding="UTF-8"?>
+<ListBucketResult xmlns="http://s3.amazonaws.com/doc/2006-03-01/">
+    <Name>test-bucket</Name>
+    <Prefix></Prefix>
+    <KeyCount>2</KeyCount>
+    <MaxKeys>1000</MaxKeys>
+    <IsTruncated>false</IsTruncated>
+    <Contents>
+        <Key>small.txt</Key>
+        <LastModified>2021-01-01T00:00:00.000Z</LastModified>
+        <Size>1</Size>
+        <StorageClass>STANDARD</StorageClass>
+    </Contents>
+    <Contents>
+        <Key>big.txt</Key>
+        <LastModified>2021-06-01T00:00:00.000Z</LastModified>
+        <Size>1000</Size>
+        <StorageClass>STANDARD</StorageClass>
+    </Contents>
+</ListBucketResult>"#;
+
+#[tokio::test]
+async fn lists_directory_contents_sorted_by_size_descending() {
+    let mock_server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/test-bucket"))
+        .respond_with(ResponseTemplate::new(200).set_body_string(LIST_OBJECTS_XML_TWO_ENTRIES))
+        .mount(&mock_server)
+        .await;
+
+    let opt = opt_for(&mock_server, None).await;
+    let file_config = FileConfig::default();
+    let ctx = build_ctx(&opt, &file_config).await;
+    let route = build_route(ctx);
+
+    let resp = warp::test::request()
+        .path("/?sort=size&order=desc")
+        .reply(&route)
+        .await;
+    assert_eq!(resp.status(), 200);
+    let body = String::from_utf8_lossy(resp.body());
+    let big_pos = body.find("big.txt").expect("big.txt missing from listing");
+    let small_pos = body
+        .find("small.txt")
+        .expect("small.txt missing from listing");
+    assert!(
+        big_pos < small_pos,
+        "expected big.txt before small.txt when sorting by size desc"
+    );
+}
+
+#[tokio::test]
+async fn proxies_object_bytes() {
+    let mock_server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/test-bucket/hello.txt"))
+        .respond_with(ResponseTemplate::new(200).set_body_string("world"))
+        .mount(&mock_server)
+        .await;
+
+    let opt = opt_for(&mock_server, None).await;
+    let file_config = FileConfig::default();
+    let ctx = build_ctx(&opt, &file_config).await;
+    let route = build_route(ctx);
+
+    let resp = warp::test::request().path("/hello.txt").reply(&route).await;
+    assert_eq!(resp.status(), 200);
+    assert_eq!(resp.body(), "world");
+}
+
+#[tokio::test]
+async fn config_file_fills_in_unset_flags() {
+    let mut config_file = tempfile::NamedTempFile::new().unwrap();
+    writeln!(
+        config_file,
+        r#"
+        bucket = "from-config-file"
+        listen = "127.0.0.1:4040"
+        "#
+    )
+    .unwrap();
+
+    let opt = Opt {
+        bucket: None,
+        region: None,
+        endpoint: None,
+        mode: None,
+        backend: None,
+        max_retries: None,
+        listen: None,
+        presign_expiry_secs: None,
+        config: Some(config_file.path().to_path_buf()),
+    };
+    let file_config = FileConfig::load(config_file.path()).unwrap();
+    assert_eq!(file_config.bucket.as_deref(), Some("from-config-file"));
+
+    let listen_address = resolve_listen_address(&opt, &file_config);
+    assert_eq!(listen_address.to_string(), "127.0.0.1:4040");
+}